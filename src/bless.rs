@@ -0,0 +1,145 @@
+//! Interactive, per-test review of pending blesses. Instead of
+//! [`TestResult::save_results`] overwriting every mismatched/missing
+//! expect file unconditionally, this walks each one, shows its diff,
+//! and lets the user accept, skip, or quit.
+
+use crate::errors::RuntError;
+use crate::test_results::{TestResult, TestState};
+use std::io::Write;
+
+/// Number of unchanged lines kept around each changed hunk when
+/// displaying a diff for review, mirroring rustfmt's
+/// `DIFF_CONTEXT_SIZE`.
+const DIFF_CONTEXT_SIZE: usize = 3;
+
+/// What the user chose to do with one pending change.
+enum Choice {
+    Accept,
+    Skip,
+    Quit,
+}
+
+/// Bless `results` the way `opts` asks for: interactively (one at a
+/// time, via `review`) when `opts.review` is set, taking precedence
+/// over unconditionally blessing everything when `opts.save` is set.
+pub fn bless(results: &[TestResult], opts: &crate::cli::Opts) -> Result<(), RuntError> {
+    if opts.review {
+        review(results)?;
+        return Ok(());
+    }
+    if opts.save {
+        for result in results {
+            result.save_results()?;
+        }
+    }
+    Ok(())
+}
+
+/// Walk every mismatched/missing result in `results`, prompting the
+/// user for each one and calling [`TestResult::save_results`] only on
+/// accepted tests. Returns the number of tests that were blessed.
+pub fn review(results: &[TestResult]) -> Result<usize, RuntError> {
+    use colored::*;
+    use TestState as TS;
+
+    let mut blessed = 0;
+    for result in results {
+        let (expect_string, contents) = match &result.state {
+            TS::Missing(expect) => (expect.as_str(), ""),
+            TS::Mismatch(expect, contents) => (expect.as_str(), contents.as_str()),
+            TS::Correct | TS::KnownFail(_) | TS::XPass => continue,
+        };
+
+        println!("{}", result.path.to_str().unwrap().bold());
+        println!(
+            "{}",
+            bounded_diff(&crate::diff::gen_diff(contents, expect_string), DIFF_CONTEXT_SIZE)
+        );
+
+        match prompt()? {
+            Choice::Accept => {
+                result.save_results()?;
+                blessed += 1;
+            }
+            Choice::Skip => continue,
+            Choice::Quit => break,
+        }
+    }
+    Ok(blessed)
+}
+
+/// Prompt `[a]ccept/[s]kip/[q]uit` on stdin, repeating on
+/// unrecognized input.
+fn prompt() -> Result<Choice, RuntError> {
+    loop {
+        print!("accept this change? [a]ccept/[s]kip/[q]uit: ");
+        std::io::stdout().flush()?;
+
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        match line.trim() {
+            "a" | "accept" => return Ok(Choice::Accept),
+            "s" | "skip" => return Ok(Choice::Skip),
+            "q" | "quit" => return Ok(Choice::Quit),
+            _ => continue,
+        }
+    }
+}
+
+/// Trim `diff` down to `context` unchanged lines around each changed
+/// (`+`/`-`-prefixed) line, collapsing longer unchanged runs with an
+/// ellipsis so large outputs don't flood the terminal.
+fn bounded_diff(diff: &str, context: usize) -> String {
+    let lines: Vec<&str> = diff.lines().collect();
+    let changed: Vec<bool> = lines
+        .iter()
+        .map(|line| line.starts_with('+') || line.starts_with('-'))
+        .collect();
+
+    let mut keep = vec![false; lines.len()];
+    for (i, &is_changed) in changed.iter().enumerate() {
+        if is_changed {
+            let start = i.saturating_sub(context);
+            let end = (i + context + 1).min(lines.len());
+            for slot in keep.iter_mut().take(end).skip(start) {
+                *slot = true;
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let mut skipped = false;
+    for (i, line) in lines.iter().enumerate() {
+        if keep[i] {
+            out.push_str(line);
+            out.push('\n');
+            skipped = false;
+        } else if !skipped {
+            out.push_str("...\n");
+            skipped = true;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_everything_within_context() {
+        let diff = "a\n+b\nc";
+        assert_eq!(bounded_diff(diff, 3), "a\n+b\nc\n");
+    }
+
+    #[test]
+    fn collapses_runs_longer_than_context() {
+        let diff = "1\n2\n3\n4\n5\n+6\n7\n8\n9\n10\n11";
+        assert_eq!(bounded_diff(diff, 1), "...\n5\n+6\n7\n...\n");
+    }
+
+    #[test]
+    fn no_changed_lines_collapses_to_single_ellipsis() {
+        assert_eq!(bounded_diff("a\nb\nc", 1), "...\n");
+    }
+}
@@ -0,0 +1,68 @@
+//! Command-line options for a `runt` invocation.
+
+use std::path::PathBuf;
+
+/// Command-line options for a `runt` invocation.
+#[derive(Debug, clap::Parser)]
+pub struct Opts {
+    /// Directories containing test suites to run.
+    pub dir: Vec<PathBuf>,
+
+    /// Show a diff for failing/missing tests.
+    #[clap(long)]
+    pub diff: bool,
+
+    /// Only show tests matching this outcome.
+    #[clap(long)]
+    pub only: Option<OnlyOpt>,
+
+    /// Emit a machine-readable JSON report instead of colorized text.
+    #[clap(long)]
+    pub json: bool,
+
+    /// Bless mismatched/missing expect files.
+    #[clap(long)]
+    pub save: bool,
+
+    /// Bless interactively: review each pending change's diff and
+    /// accept/skip/quit, instead of blessing everything `--save`
+    /// would. Takes precedence over `--save`. See
+    /// [`crate::bless::review`].
+    #[clap(long)]
+    pub review: bool,
+
+    /// Path to a TOML file configuring this suite (currently: the
+    /// stdout/stderr normalization filters run before comparison).
+    /// See [`crate::config::SuiteConfig`].
+    #[clap(long)]
+    pub config: Option<PathBuf>,
+
+    /// Path to a `runt-known-failures.toml` file listing tests that
+    /// are currently known to fail. See
+    /// [`crate::known_failures::KnownFailures`].
+    #[clap(long)]
+    pub known_failures: Option<PathBuf>,
+
+    /// Named revision to additionally run each test under (e.g.
+    /// `--revision opt-a --revision opt-b`), comparing against
+    /// per-stream expect files scoped to that revision. See
+    /// [`crate::test_results::compare_revisions`].
+    #[clap(long = "revision")]
+    pub revisions: Vec<String>,
+}
+
+/// Which subset of results to show, selected via `--only`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnlyOpt {
+    Pass,
+    Fail,
+    Missing,
+    /// Tests listed in the known-failures file (see
+    /// `--known-failures`) whose `Mismatch`/`Missing` outcome is
+    /// expected.
+    #[clap(name = "known-fail")]
+    KnownFail,
+    /// Tests listed in the known-failures file that unexpectedly
+    /// passed.
+    XPass,
+}
@@ -0,0 +1,55 @@
+//! Per-test-suite configuration loaded from the TOML file pointed at
+//! by `--config` (see [`crate::cli::Opts::config`]).
+
+use crate::errors::RuntError;
+use crate::filters::OutputFilter;
+use serde::Deserialize;
+
+/// On-disk shape of a suite config file.
+#[derive(Debug, Deserialize, Default)]
+struct RawSuiteConfig {
+    #[serde(default)]
+    stdout_filters: Vec<RawFilter>,
+    #[serde(default)]
+    stderr_filters: Vec<RawFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFilter {
+    pattern: String,
+    replacement: String,
+}
+
+/// Parsed, regex-compiled suite configuration.
+#[derive(Debug, Default)]
+pub struct SuiteConfig {
+    pub stdout_filters: Vec<OutputFilter>,
+    pub stderr_filters: Vec<OutputFilter>,
+}
+
+impl SuiteConfig {
+    /// Load and compile a suite config file from `path`.
+    pub fn from_file(path: &std::path::Path) -> Result<Self, RuntError> {
+        let contents = std::fs::read_to_string(path)?;
+        let raw: RawSuiteConfig = toml::from_str(&contents)?;
+        Ok(SuiteConfig {
+            stdout_filters: compile_filters(raw.stdout_filters)?,
+            stderr_filters: compile_filters(raw.stderr_filters)?,
+        })
+    }
+
+    /// The empty configuration, used when `--config` isn't passed.
+    pub fn empty() -> Self {
+        SuiteConfig::default()
+    }
+}
+
+fn compile_filters(filters: Vec<RawFilter>) -> Result<Vec<OutputFilter>, RuntError> {
+    filters
+        .into_iter()
+        .map(|filter| {
+            let regex = regex::Regex::new(&filter.pattern)?;
+            Ok(OutputFilter::new(regex, filter.replacement))
+        })
+        .collect()
+}
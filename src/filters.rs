@@ -0,0 +1,59 @@
+/// A single output-normalization filter: a pattern matched against
+/// captured stdout/stderr, paired with the text that should replace
+/// every match before the output is turned into an expect string.
+#[derive(Debug, Clone)]
+pub struct OutputFilter {
+    /// Pattern matched against the raw output.
+    pub regex: regex::Regex,
+    /// Text each match is replaced with.
+    pub replacement: String,
+}
+
+impl OutputFilter {
+    pub fn new(regex: regex::Regex, replacement: impl Into<String>) -> Self {
+        Self {
+            regex,
+            replacement: replacement.into(),
+        }
+    }
+}
+
+/// Run `text` through `filters`, in order, replacing every match of
+/// each filter's regex with its replacement text, taken literally
+/// (never interpreted as a `$1`-style capture-group reference).
+pub fn apply_filters(text: &str, filters: &[OutputFilter]) -> String {
+    filters.iter().fold(text.to_string(), |acc, filter| {
+        filter
+            .regex
+            .replace_all(&acc, regex::NoExpand(&filter.replacement))
+            .into_owned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_every_match() {
+        let filter = OutputFilter::new(regex::Regex::new(r"\d+").unwrap(), "N");
+        assert_eq!(apply_filters("at 1 and 2 and 3", &[filter]), "at N and N and N");
+    }
+
+    #[test]
+    fn applies_filters_in_order() {
+        let filters = vec![
+            OutputFilter::new(regex::Regex::new("a").unwrap(), "b"),
+            OutputFilter::new(regex::Regex::new("b").unwrap(), "c"),
+        ];
+        // "a" -> "b" -> "c", so the second filter also catches what
+        // the first one produced.
+        assert_eq!(apply_filters("a", &filters), "c");
+    }
+
+    #[test]
+    fn replacement_text_is_literal_not_a_capture_reference() {
+        let filter = OutputFilter::new(regex::Regex::new(r"(\d+)").unwrap(), "$1 literally");
+        assert_eq!(apply_filters("id 42", &[filter]), "id $1 literally");
+    }
+}
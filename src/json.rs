@@ -0,0 +1,110 @@
+//! Structured, machine-readable reporting for `TestSuiteResult`.
+
+use crate::errors::RuntError;
+use crate::test_results::{TestResult, TestState, TestSuiteResult};
+use serde::Serialize;
+
+/// Status of a single test, as reported in JSON.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonStatus {
+    Pass,
+    Miss,
+    Fail,
+    KnownFail,
+    XPass,
+}
+
+/// JSON representation of one [`TestResult`].
+#[derive(Debug, Serialize)]
+pub struct JsonTestResult {
+    pub path: std::path::PathBuf,
+    pub status: JsonStatus,
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+impl From<&TestResult> for JsonTestResult {
+    fn from(result: &TestResult) -> Self {
+        let (status, diff) = match &result.state {
+            TestState::Correct => (JsonStatus::Pass, None),
+            TestState::Missing(_) => (JsonStatus::Miss, None),
+            TestState::Mismatch(expect_string, contents) => (
+                JsonStatus::Fail,
+                Some(crate::diff::gen_diff(contents, expect_string)),
+            ),
+            TestState::KnownFail(_) => (JsonStatus::KnownFail, None),
+            TestState::XPass => (JsonStatus::XPass, None),
+        };
+        JsonTestResult {
+            path: result.path.clone(),
+            status,
+            exit_code: result.status,
+            stdout: result.stdout.clone(),
+            stderr: result.stderr.clone(),
+            diff,
+        }
+    }
+}
+
+/// Summary counts for a whole suite.
+#[derive(Debug, Serialize)]
+pub struct JsonSummary {
+    pub pass: usize,
+    pub miss: usize,
+    pub fail: usize,
+    pub known_fail: usize,
+    pub xpass: usize,
+}
+
+/// JSON representation of a whole [`TestSuiteResult`].
+#[derive(Debug, Serialize)]
+pub struct JsonTestSuiteResult {
+    pub name: String,
+    pub tests: Vec<JsonTestResult>,
+    pub summary: JsonSummary,
+    pub errors: Vec<String>,
+}
+
+impl From<&TestSuiteResult> for JsonTestSuiteResult {
+    fn from(suite: &TestSuiteResult) -> Self {
+        let TestSuiteResult(name, results, errors) = suite;
+        let tests: Vec<JsonTestResult> = results.iter().map(JsonTestResult::from).collect();
+
+        let summary = tests.iter().fold(
+            JsonSummary {
+                pass: 0,
+                miss: 0,
+                fail: 0,
+                known_fail: 0,
+                xpass: 0,
+            },
+            |mut summary, test| {
+                match test.status {
+                    JsonStatus::Pass => summary.pass += 1,
+                    JsonStatus::Miss => summary.miss += 1,
+                    JsonStatus::Fail => summary.fail += 1,
+                    JsonStatus::KnownFail => summary.known_fail += 1,
+                    JsonStatus::XPass => summary.xpass += 1,
+                }
+                summary
+            },
+        );
+
+        JsonTestSuiteResult {
+            name: name.clone(),
+            tests,
+            summary,
+            errors: errors.iter().map(RuntError::to_string).collect(),
+        }
+    }
+}
+
+/// Serialize `suite` as a pretty-printed JSON document.
+pub fn report_json(suite: &TestSuiteResult) -> Result<String, RuntError> {
+    let json = JsonTestSuiteResult::from(suite);
+    Ok(serde_json::to_string_pretty(&json)?)
+}
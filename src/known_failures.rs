@@ -0,0 +1,93 @@
+//! A TOML-driven allowlist of tests that are currently broken. Lets a
+//! large suite track outstanding failures without deleting their
+//! expect files or failing CI on them.
+
+use crate::errors::RuntError;
+use glob::Pattern;
+use serde::Deserialize;
+
+/// On-disk shape of a `runt-known-failures.toml` file.
+#[derive(Debug, Deserialize)]
+struct RawKnownFailures {
+    /// Glob patterns (relative to the suite root) of tests that are
+    /// currently known to fail.
+    #[serde(default)]
+    ignored: Vec<String>,
+}
+
+/// Parsed, glob-compiled contents of a `runt-known-failures.toml`
+/// file.
+#[derive(Debug)]
+pub struct KnownFailures {
+    ignored: Vec<Pattern>,
+}
+
+impl KnownFailures {
+    /// Load a known-failures file from `path`. Each `ignored` entry
+    /// is compiled as a glob up front, so a typo'd pattern is an
+    /// error at load time rather than a silent no-op at match time.
+    pub fn from_file(path: &std::path::Path) -> Result<Self, RuntError> {
+        let contents = std::fs::read_to_string(path)?;
+        let raw: RawKnownFailures = toml::from_str(&contents)?;
+        let ignored = raw
+            .ignored
+            .into_iter()
+            .map(|pattern| Pattern::new(&pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(KnownFailures { ignored })
+    }
+
+    /// An empty list, used when no known-failures file is configured.
+    pub fn empty() -> Self {
+        KnownFailures { ignored: vec![] }
+    }
+
+    /// Whether `path` is listed as a known failure.
+    pub fn contains(&self, path: &std::path::Path) -> bool {
+        self.ignored.iter().any(|pattern| pattern.matches_path(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `contents` to a uniquely-named file under the system
+    /// temp dir and return its path, for round-tripping `from_file`.
+    fn known_failures_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn empty_matches_nothing() {
+        assert!(!KnownFailures::empty().contains(std::path::Path::new("foo/bar.rs")));
+    }
+
+    #[test]
+    fn matches_glob_pattern_from_file() {
+        let path = known_failures_file(
+            "runt-known-failures-match.toml",
+            "ignored = [\"tests/broken/*.rs\"]\n",
+        );
+        let known = KnownFailures::from_file(&path).unwrap();
+        assert!(known.contains(std::path::Path::new("tests/broken/foo.rs")));
+        assert!(!known.contains(std::path::Path::new("tests/ok/foo.rs")));
+    }
+
+    #[test]
+    fn malformed_toml_is_an_error() {
+        let path = known_failures_file("runt-known-failures-malformed.toml", "this is not valid toml");
+        assert!(KnownFailures::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn invalid_glob_pattern_is_an_error() {
+        let path = known_failures_file(
+            "runt-known-failures-bad-glob.toml",
+            "ignored = [\"tests/[unterminated\"]\n",
+        );
+        assert!(KnownFailures::from_file(&path).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Loose matching between a stored `.expect` file and the output a
+//! test actually produced: the expect file is treated as a pattern
+//! rather than a literal, so a test can tolerate known-variable
+//! substrings without needing a full [`crate::filters`] regex.
+
+use regex::Regex;
+
+/// Token meaning "match any run of characters" within a single line
+/// of an expect pattern.
+const WILDCARD: &str = "[..]";
+
+/// Escape sequence allowing a literal `[..]` to appear in a pattern.
+const WILDCARD_ESCAPE: &str = r"\[..]";
+
+/// Result of comparing an expected pattern against actual output.
+#[derive(Debug, PartialEq)]
+pub enum PatternMatch {
+    /// Every line matched.
+    Matches,
+    /// The first mismatching line (1-indexed), with the expected
+    /// pattern and actual contents of that line.
+    Mismatch {
+        line: usize,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Compare `expected` (a pattern, as read from an expect file) against
+/// `actual` (the normalized test output) line by line.
+pub fn compare(expected: &str, actual: &str) -> PatternMatch {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+
+    for (i, expected_line) in expected_lines.iter().enumerate() {
+        let matches = match actual_lines.get(i) {
+            // A missing line never matches, even against a bare
+            // wildcard: `[..]` stands for "any run of characters
+            // within this line", not "this line is optional".
+            None => false,
+            Some(actual_line) => line_matches(expected_line, actual_line),
+        };
+        if !matches {
+            return PatternMatch::Mismatch {
+                line: i + 1,
+                expected: expected_line.to_string(),
+                actual: actual_lines.get(i).copied().unwrap_or("").to_string(),
+            };
+        }
+    }
+
+    if actual_lines.len() > expected_lines.len() {
+        return PatternMatch::Mismatch {
+            line: expected_lines.len() + 1,
+            expected: String::new(),
+            actual: actual_lines[expected_lines.len()].to_string(),
+        };
+    }
+
+    PatternMatch::Matches
+}
+
+/// Match a single actual line against a single expected pattern line.
+/// Lines without `[..]` must match exactly.
+fn line_matches(expected: &str, actual: &str) -> bool {
+    if !expected.contains(WILDCARD) {
+        return expected == actual;
+    }
+    pattern_regex(expected).is_match(actual)
+}
+
+/// Compile an expect pattern line into an anchored regex, turning
+/// `[..]` into `.*` and escaping everything else. `\[..]` is
+/// unescaped back into a literal `[..]` rather than a wildcard.
+fn pattern_regex(line: &str) -> Regex {
+    const SENTINEL: &str = "\u{0}";
+    let without_escapes = line.replace(WILDCARD_ESCAPE, SENTINEL);
+
+    let mut pattern = String::from("^");
+    for (i, segment) in without_escapes.split(WILDCARD).enumerate() {
+        if i > 0 {
+            pattern.push_str(".*");
+        }
+        let escaped = regex::escape(segment).replace(SENTINEL, r"\[\.\.\]");
+        pattern.push_str(&escaped);
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern).expect("expect pattern line produced an invalid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_single_wildcard() {
+        assert_eq!(compare("hello [..]!", "hello world!"), PatternMatch::Matches);
+    }
+
+    #[test]
+    fn matches_multiple_wildcards_on_one_line() {
+        assert_eq!(
+            compare("[..] took [..]ms", "test foo took 42ms"),
+            PatternMatch::Matches
+        );
+    }
+
+    #[test]
+    fn escaped_wildcard_is_literal() {
+        assert_eq!(compare(r"literal \[..] here", "literal [..] here"), PatternMatch::Matches);
+        assert_eq!(
+            compare(r"literal \[..] here", "literal anything here"),
+            PatternMatch::Mismatch {
+                line: 1,
+                expected: r"literal \[..] here".to_string(),
+                actual: "literal anything here".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_first_mismatching_line() {
+        let expected = "one\ntwo\nthree";
+        let actual = "one\nTWO\nthree";
+        assert_eq!(
+            compare(expected, actual),
+            PatternMatch::Mismatch {
+                line: 2,
+                expected: "two".to_string(),
+                actual: "TWO".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_trailing_wildcard_line_is_a_mismatch() {
+        assert_eq!(
+            compare("foo\n[..]", "foo"),
+            PatternMatch::Mismatch {
+                line: 2,
+                expected: "[..]".to_string(),
+                actual: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn trailing_extra_line_is_a_mismatch() {
+        let expected = "one\ntwo";
+        let actual = "one\ntwo\nthree";
+        assert_eq!(
+            compare(expected, actual),
+            PatternMatch::Mismatch {
+                line: 3,
+                expected: String::new(),
+                actual: "three".to_string(),
+            }
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use crate::cli;
 use crate::errors::RuntError;
+use crate::filters::{apply_filters, OutputFilter};
+use crate::pattern;
 
 /// Track the state of TestResult.
 #[derive(Debug, PartialEq)]
@@ -10,10 +12,22 @@ pub enum TestState {
     Missing(String),
     /// The comparison failed. Contains the the generated expectation string
     /// and the contents of the expect file.
+    ///
+    /// The expect file's contents are treated as a pattern, not a
+    /// literal: a `[..]` token matches any run of characters within
+    /// the line it appears on. See [`crate::pattern`].
     Mismatch(
         String, // Generated expect string.
         String, // Contents of the expect file.
     ),
+    /// The test is listed in a known-failures file, so a `Mismatch`
+    /// or `Missing` outcome is expected and does not fail the run.
+    /// Contains the generated expectation string the test produced.
+    KnownFail(String),
+    /// The test is listed in a known-failures file but the comparison
+    /// actually succeeded (an "unexpected pass"). Reported so stale
+    /// allowlist entries get noticed instead of silently lingering.
+    XPass,
 }
 
 /// Store information related to one test.
@@ -33,21 +47,74 @@ pub struct TestResult {
 
     /// Result of comparison
     pub state: TestState,
+
+    /// Name of the revision this result was run under (e.g.
+    /// `opt-a`), if it was run under a named revision. `None` for a
+    /// test run under its single, unnamed configuration.
+    pub revision: Option<String>,
+
+    /// Per-stream expect files that need blessing, as produced by
+    /// [`compare_split`]. Empty for a test using the combined
+    /// `.expect` format, in which case `save_results` (not
+    /// `save_results_split`) is the right way to bless this result.
+    pub pending_streams: Vec<ExpectKind>,
 }
 
 impl TestResult {
+    /// Downgrade this result if its test is listed in `known`: a
+    /// `Mismatch`/`Missing` becomes `KnownFail`, and a `Correct`
+    /// becomes `XPass` so the stale allowlist entry is surfaced.
+    pub fn apply_known_failures(mut self, known: &crate::known_failures::KnownFailures) -> Self {
+        use TestState as TS;
+        if known.contains(&self.path) {
+            self.state = match self.state {
+                TS::Correct => TS::XPass,
+                TS::Missing(expect) | TS::Mismatch(expect, _) => TS::KnownFail(expect),
+                other @ (TS::KnownFail(_) | TS::XPass) => other,
+            };
+        }
+        self
+    }
+
     /// Save the results of the test suite into the expect file.
+    ///
+    /// If this result came from [`compare_split`] and has pending
+    /// per-stream expect files, delegates to `save_results_split`
+    /// instead of writing the synthetic combined dump into a bogus
+    /// `.expect` file.
     pub fn save_results(&self) -> Result<(), RuntError> {
         use std::fs;
         use TestState as TS;
+        if !self.pending_streams.is_empty() {
+            return self.save_results_split(&self.pending_streams);
+        }
         match &self.state {
-            TS::Correct => Ok(()),
+            TS::Correct | TS::KnownFail(_) | TS::XPass => Ok(()),
             TS::Missing(expect) | TS::Mismatch(expect, _) => {
                 Ok(fs::write(expect_file(&self.path), expect)?)
             }
         }
     }
 
+    /// Bless `streams`, writing each into its own per-stream expect
+    /// file (`.exit`/`.stdout`/`.stderr`, scoped to `self.revision` if
+    /// any) instead of the single combined `.expect` file. Streams
+    /// that already matched are left untouched by the caller, so each
+    /// stream blesses independently.
+    pub fn save_results_split(&self, streams: &[ExpectKind]) -> Result<(), RuntError> {
+        use std::fs;
+        for kind in streams {
+            let path = expected_output_path(&self.path, self.revision.as_deref(), *kind);
+            let contents = match kind {
+                ExpectKind::Exit => self.status.to_string(),
+                ExpectKind::Stdout => self.stdout.clone(),
+                ExpectKind::Stderr => self.stderr.clone(),
+            };
+            fs::write(path, contents)?;
+        }
+        Ok(())
+    }
+
     /// Generate colorized string to report the results of this test.
     pub fn report_str(&self, show_diff: bool) -> String {
         use crate::diff;
@@ -55,7 +122,11 @@ impl TestResult {
         use TestState as TS;
 
         let mut buf = String::new();
-        let path_str = self.path.to_str().unwrap();
+        let path_str = match &self.revision {
+            Some(revision) => format!("{} [{}]", self.path.to_str().unwrap(), revision),
+            None => self.path.to_str().unwrap().to_string(),
+        };
+        let path_str = path_str.as_str();
         match &self.state {
             TS::Missing(expect_string) => {
                 buf.push_str(&"⚬ miss - ".yellow().to_string());
@@ -73,11 +144,35 @@ impl TestResult {
                 buf.push_str(&"⚬ fail - ".red().to_string());
                 buf.push_str(&path_str.red().to_string());
                 if show_diff {
+                    if let pattern::PatternMatch::Mismatch { line, expected, actual } =
+                        pattern::compare(&contents, &expect_string)
+                    {
+                        buf.push_str("\n");
+                        buf.push_str(&format!(
+                            "{} {}:\n  expected: {}\n  actual:   {}",
+                            "first mismatching line".red(),
+                            line,
+                            expected,
+                            actual,
+                        ));
+                    }
                     let diff = diff::gen_diff(&contents, &expect_string);
                     buf.push_str("\n");
                     buf.push_str(&diff);
                 }
             }
+            TS::KnownFail(expect_string) => {
+                buf.push_str(&"⚬ known-fail - ".cyan().to_string());
+                buf.push_str(&path_str.cyan().to_string());
+                if show_diff {
+                    buf.push_str("\n");
+                    buf.push_str(&expect_string);
+                }
+            }
+            TS::XPass => {
+                buf.push_str(&"⚬ xpass - ".magenta().to_string());
+                buf.push_str(&path_str.magenta().to_string());
+            }
         };
         buf.to_string()
     }
@@ -96,6 +191,8 @@ impl TestSuiteResult {
                     (O::Fail, TS::Mismatch(..)) => true,
                     (O::Pass, TS::Correct) => true,
                     (O::Missing, TS::Missing(..)) => true,
+                    (O::KnownFail, TS::KnownFail(..)) => true,
+                    (O::XPass, TS::XPass) => true,
                     _ => false,
                 };
             }
@@ -105,12 +202,25 @@ impl TestSuiteResult {
     }
 
     /// Print the results of running this test suite.
+    ///
+    /// When `opts.json` is set, emits the structured document from
+    /// [`crate::json::report_json`] instead of the default colorized
+    /// text, for consumption by CI dashboards.
     pub fn print_test_suite_results(
         self: TestSuiteResult,
         opts: &cli::Opts,
         num_tests: usize,
     ) {
         use colored::*;
+
+        if opts.json {
+            match crate::json::report_json(&self) {
+                Ok(json) => println!("{}", json),
+                Err(err) => eprintln!("{}", err.to_string().red()),
+            }
+            return;
+        }
+
         let TestSuiteResult(name, results, errors) = self;
 
         println!("{} ({} tests)", name.bold(), num_tests);
@@ -136,10 +246,15 @@ impl TestSuiteResult {
 /// <contents of STDOUT>
 /// ---STDERR---
 /// <contents of STDERR>
+///
+/// `stdout`/`stderr` are run through `stdout_filters`/`stderr_filters`
+/// before being embedded.
 pub fn to_expect_string(
     status: &i32,
     stdout: &String,
     stderr: &String,
+    stdout_filters: &[OutputFilter],
+    stderr_filters: &[OutputFilter],
 ) -> String {
     let mut buf = String::new();
     buf.push_str("---CODE---\n");
@@ -147,15 +262,182 @@ pub fn to_expect_string(
     buf.push('\n');
 
     buf.push_str("---STDOUT---\n");
-    buf.push_str(stdout.as_str());
+    buf.push_str(apply_filters(stdout, stdout_filters).as_str());
 
     buf.push_str("---STDERR---\n");
-    buf.push_str(stderr.as_str());
+    buf.push_str(apply_filters(stderr, stderr_filters).as_str());
 
     buf.to_string()
 }
 
+/// Decide whether `generated` (this run's expect string) satisfies
+/// `stored` (the contents of the `.expect` file), honoring the
+/// `[..]` wildcard pattern described on [`TestState::Mismatch`].
+pub fn expects_match(generated: &str, stored: &str) -> bool {
+    pattern::compare(stored, generated) == pattern::PatternMatch::Matches
+}
+
 /// Path of the expect file.
 pub fn expect_file(path: &std::path::PathBuf) -> std::path::PathBuf {
     path.as_path().with_extension("expect")
 }
+
+/// One of the three streams that can be blessed independently when a
+/// test uses per-stream expect files instead of a single combined
+/// `.expect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectKind {
+    /// The `.exit` file, holding the exit code.
+    Exit,
+    /// The `.stdout` file.
+    Stdout,
+    /// The `.stderr` file.
+    Stderr,
+}
+
+impl ExpectKind {
+    fn extension(self) -> &'static str {
+        match self {
+            ExpectKind::Exit => "exit",
+            ExpectKind::Stdout => "stdout",
+            ExpectKind::Stderr => "stderr",
+        }
+    }
+}
+
+/// Resolve the on-disk path of a per-stream expect file for `path`,
+/// following compiletest's `expected_output_path(testpaths, revision,
+/// kind)`: `foo.stdout`, or `foo.opt-a.stdout` when `revision` is
+/// `Some("opt-a")`.
+pub fn expected_output_path(
+    path: &std::path::PathBuf,
+    revision: Option<&str>,
+    kind: ExpectKind,
+) -> std::path::PathBuf {
+    let extension = match revision {
+        Some(revision) => format!("{}.{}", revision, kind.extension()),
+        None => kind.extension().to_string(),
+    };
+    path.as_path().with_extension(extension)
+}
+
+/// Compare a test's captured output against whichever per-stream
+/// expect files exist on disk for `path`/`revision`. Only streams
+/// with an existing expect file are checked, so a test that ships
+/// only `foo.stdout` is never held to `foo.exit`/`foo.stderr`; each
+/// stream is compared (and later blessed) independently of the
+/// others.
+///
+/// Returns the rolled-up `TestState` for the revision, alongside the
+/// list of streams that would need blessing to make it `Correct`.
+pub fn compare_split(
+    path: &std::path::PathBuf,
+    revision: Option<&str>,
+    status: &i32,
+    stdout: &str,
+    stderr: &str,
+    stdout_filters: &[OutputFilter],
+    stderr_filters: &[OutputFilter],
+) -> (TestState, Vec<ExpectKind>) {
+    use std::fs;
+
+    let generated = [
+        (ExpectKind::Exit, status.to_string()),
+        (ExpectKind::Stdout, apply_filters(stdout, stdout_filters)),
+        (ExpectKind::Stderr, apply_filters(stderr, stderr_filters)),
+    ];
+
+    let mut pending = Vec::new();
+    let mut any_missing = false;
+    let mut generated_combined = String::new();
+    let mut stored_combined = String::new();
+
+    // A brand-new split-mode/revision test has no expect files at all;
+    // that must surface as "every stream needs blessing", not "nothing
+    // to check". Once at least one stream has been blessed, an absent
+    // sibling means the author opted out of checking it.
+    let any_expect_file_exists = generated
+        .iter()
+        .any(|(kind, _)| expected_output_path(path, revision, *kind).exists());
+
+    for (kind, generated_text) in &generated {
+        let expect_path = expected_output_path(path, revision, *kind);
+        if !expect_path.exists() {
+            if any_expect_file_exists {
+                continue;
+            }
+            any_missing = true;
+            pending.push(*kind);
+            generated_combined.push_str(&format!("---{:?}---\n{}\n", kind, generated_text));
+            continue;
+        }
+        match fs::read_to_string(&expect_path) {
+            Ok(stored) if expects_match(generated_text, &stored) => {}
+            Ok(stored) => {
+                pending.push(*kind);
+                generated_combined.push_str(&format!("---{:?}---\n{}\n", kind, generated_text));
+                stored_combined.push_str(&format!("---{:?}---\n{}\n", kind, stored));
+            }
+            Err(_) => {
+                any_missing = true;
+                pending.push(*kind);
+                generated_combined.push_str(&format!("---{:?}---\n{}\n", kind, generated_text));
+            }
+        }
+    }
+
+    let state = if pending.is_empty() {
+        TestState::Correct
+    } else if any_missing && stored_combined.is_empty() {
+        TestState::Missing(generated_combined)
+    } else {
+        TestState::Mismatch(generated_combined, stored_combined)
+    };
+
+    (state, pending)
+}
+
+/// One test run under a single named revision, already executed and
+/// captured by the caller (e.g. once per `--opt-a`/`--opt-b` flag
+/// set).
+pub struct RevisionRun<'a> {
+    pub revision: String,
+    pub status: i32,
+    pub stdout: &'a str,
+    pub stderr: &'a str,
+}
+
+/// Run [`compare_split`] once per entry in `runs`, producing one
+/// `TestResult` per revision as required when a test declares named
+/// revisions.
+pub fn compare_revisions(
+    path: &std::path::PathBuf,
+    runs: &[RevisionRun],
+    stdout_filters: &[OutputFilter],
+    stderr_filters: &[OutputFilter],
+) -> Vec<TestResult> {
+    runs.iter()
+        .map(|run| {
+            let (state, pending_streams) = compare_split(
+                path,
+                Some(run.revision.as_str()),
+                &run.status,
+                run.stdout,
+                run.stderr,
+                stdout_filters,
+                stderr_filters,
+            );
+            // Store the normalized streams, so `save_results_split`
+            // blesses the same text `compare_split` compared.
+            TestResult {
+                path: path.clone(),
+                status: run.status,
+                stdout: apply_filters(run.stdout, stdout_filters),
+                stderr: apply_filters(run.stderr, stderr_filters),
+                state,
+                revision: Some(run.revision.clone()),
+                pending_streams,
+            }
+        })
+        .collect()
+}